@@ -0,0 +1,529 @@
+// Command-line rotation converter, built on the headless rotation library
+use std::io::Read;
+use std::process::ExitCode;
+
+use rotation_visualizer::rotation::{
+    PASTED_MATRIX_TOLERANCE, Quaternion, QuaternionOrder, Rotation, RotationMatrix,
+};
+
+const USAGE: &str = "\
+Usage: rotconv [--from <rep>] [--to <rep> | --snippet <lang>] [--degrees] [ROTATION]
+
+Converts a rotation between representations. ROTATION is read from the
+argument, or from stdin if omitted, as numbers separated by commas and/or
+whitespace; brackets and parentheses are ignored, e.g. \"[0, 0, 0, 1]\", as
+is a numpy wrapper like \"array([0., 0., 0., 1.], dtype=float32)\".
+
+Representations:
+  quat-wxyz    quaternion, scalar first (default for --from)
+  quat-xyzw    quaternion, scalar last
+  axis-angle   x y z angle (the axis need not be unit length)
+  rotvec       rotation vector, angle times unit axis
+  matrix       3x3 rotation matrix, row-major
+
+Options:
+  --from <rep>  representation of the input, or \"auto\" to pick one from the
+                number of values (3: rotvec, 4: quaternion, wxyz unless it
+                looks like xyzw, 9: matrix); the choice is reported on stderr
+  --to <rep>    representation to print (default: all of them)
+  --snippet <lang>
+                print code constructing the rotation instead, for
+                scipy (Python), nalgebra (Rust) or eigen (C++)
+  --degrees     angles (axis-angle, rotvec) are in degrees
+  -h, --help    print this message
+
+Exit codes: 0 on success, 1 for malformed input, 2 for bad usage.";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Representation {
+    QuatWxyz,
+    QuatXyzw,
+    AxisAngle,
+    RotationVector,
+    Matrix,
+}
+
+const ALL_REPRESENTATIONS: [Representation; 5] = [
+    Representation::QuatWxyz,
+    Representation::QuatXyzw,
+    Representation::AxisAngle,
+    Representation::RotationVector,
+    Representation::Matrix,
+];
+
+impl Representation {
+    fn parse(name: &str) -> Option<Self> {
+        ALL_REPRESENTATIONS
+            .into_iter()
+            .find(|representation| representation.name() == name)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Representation::QuatWxyz => "quat-wxyz",
+            Representation::QuatXyzw => "quat-xyzw",
+            Representation::AxisAngle => "axis-angle",
+            Representation::RotationVector => "rotvec",
+            Representation::Matrix => "matrix",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Snippet {
+    Scipy,
+    Nalgebra,
+    Eigen,
+}
+
+impl Snippet {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "scipy" => Some(Snippet::Scipy),
+            "nalgebra" => Some(Snippet::Nalgebra),
+            "eigen" => Some(Snippet::Eigen),
+            _ => None,
+        }
+    }
+}
+
+struct Options {
+    // None means detect from the input ("--from auto")
+    from: Option<Representation>,
+    to: Option<Representation>,
+    snippet: Option<Snippet>,
+    degrees: bool,
+    input: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("rotconv: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let input = match options.input {
+        Some(input) => input,
+        None => {
+            let mut input = String::new();
+            if let Err(error) = std::io::stdin().read_to_string(&mut input) {
+                eprintln!("rotconv: failed to read stdin: {error}");
+                return ExitCode::from(1);
+            }
+            input
+        }
+    };
+    let numbers = match parse_numbers(&input) {
+        Ok(numbers) => numbers,
+        Err(message) => {
+            eprintln!("rotconv: {message}");
+            return ExitCode::from(1);
+        }
+    };
+    let from = match options.from {
+        Some(from) => {
+            if let Some(hint) = order_hint(from, &numbers) {
+                eprintln!("rotconv: {hint}");
+            }
+            from
+        }
+        None => match detect_representation(&numbers) {
+            Ok(from) => {
+                eprintln!("rotconv: interpreting input as {}", from.name());
+                from
+            }
+            Err(message) => {
+                eprintln!("rotconv: {message}");
+                return ExitCode::from(1);
+            }
+        },
+    };
+    let rotation = match to_rotation(from, &numbers, options.degrees) {
+        Ok(rotation) => rotation,
+        Err(message) => {
+            eprintln!("rotconv: {message}");
+            return ExitCode::from(1);
+        }
+    };
+    if let Some(snippet) = options.snippet {
+        println!("{}", code_snippet(rotation, snippet));
+        return ExitCode::SUCCESS;
+    }
+    match options.to {
+        Some(to) => println!("{}", format_as(rotation, to, options.degrees)),
+        None => {
+            for representation in ALL_REPRESENTATIONS {
+                println!(
+                    "{}: {}",
+                    representation.name(),
+                    format_as(rotation, representation, options.degrees)
+                );
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// Returns Ok(None) if help was requested
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        from: Some(Representation::QuatWxyz),
+        to: None,
+        snippet: None,
+        degrees: false,
+        input: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--degrees" => options.degrees = true,
+            "--from" | "--to" => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} needs a representation"))?;
+                if arg == "--from" && name == "auto" {
+                    options.from = None;
+                    continue;
+                }
+                let representation = Representation::parse(&name)
+                    .ok_or_else(|| format!("unknown representation '{name}'"))?;
+                if arg == "--from" {
+                    options.from = Some(representation);
+                } else {
+                    options.to = Some(representation);
+                }
+            }
+            "--snippet" => {
+                let name = args.next().ok_or("--snippet needs a language")?;
+                options.snippet = Some(
+                    Snippet::parse(&name).ok_or_else(|| format!("unknown language '{name}'"))?,
+                );
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{arg}'")),
+            _ if options.input.is_none() => options.input = Some(arg),
+            _ => return Err("expected a single ROTATION argument (quote it)".to_string()),
+        }
+    }
+    if options.to.is_some() && options.snippet.is_some() {
+        return Err("--to and --snippet cannot be combined".to_string());
+    }
+    Ok(Some(options))
+}
+
+// Also accepts numpy's repr, e.g. "array([0., 0., 0., 1.], dtype=float32)", by dropping the
+// constructor name and the dtype argument
+fn parse_numbers(input: &str) -> Result<Vec<f32>, String> {
+    let input = input.trim();
+    let input = ["np.array", "numpy.array", "array"]
+        .into_iter()
+        .find_map(|prefix| input.strip_prefix(prefix))
+        .unwrap_or(input);
+    let cleaned: String = input
+        .chars()
+        .map(|c| if "[](){},;".contains(c) { ' ' } else { c })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .filter(|token| !token.starts_with("dtype="))
+        .map(|token| {
+            token
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| format!("'{token}' is not a finite number"))
+        })
+        .collect()
+}
+
+// A non-fatal suggestion when a quaternion looks like it was written in the other order
+fn order_hint(from: Representation, numbers: &[f32]) -> Option<String> {
+    let values: [f32; 4] = numbers.try_into().ok()?;
+    let suggested = match (from, QuaternionOrder::guess(values)?) {
+        (Representation::QuatWxyz, QuaternionOrder::Xyzw) => Representation::QuatXyzw,
+        (Representation::QuatXyzw, QuaternionOrder::Wxyz) => Representation::QuatWxyz,
+        _ => return None,
+    };
+    Some(format!(
+        "hint: input looks like {0}; pass --from {0} if that was intended",
+        suggested.name()
+    ))
+}
+
+// Picks a representation for "--from auto" by the number of values. Four numbers could also be
+// xyzw or axis-angle; they default to wxyz unless they look like an xyzw identity. Nine numbers
+// can only be a matrix.
+fn detect_representation(numbers: &[f32]) -> Result<Representation, String> {
+    match numbers.len() {
+        3 => Ok(Representation::RotationVector),
+        4 => match QuaternionOrder::guess([numbers[0], numbers[1], numbers[2], numbers[3]]) {
+            Some(QuaternionOrder::Xyzw) => Ok(Representation::QuatXyzw),
+            _ => Ok(Representation::QuatWxyz),
+        },
+        9 => Ok(Representation::Matrix),
+        count => Err(format!(
+            "cannot detect a representation from {count} numbers (expected 3, 4 or 9)"
+        )),
+    }
+}
+
+fn to_rotation(from: Representation, numbers: &[f32], degrees: bool) -> Result<Rotation, String> {
+    let angle_scale = if degrees { 1f32.to_radians() } else { 1.0 };
+    let expected = match from {
+        Representation::QuatWxyz | Representation::QuatXyzw | Representation::AxisAngle => 4,
+        Representation::RotationVector => 3,
+        Representation::Matrix => 9,
+    };
+    if numbers.len() != expected {
+        return Err(format!(
+            "expected {expected} numbers for {}, got {}",
+            from.name(),
+            numbers.len()
+        ));
+    }
+    let n = numbers;
+    let rotation = match from {
+        Representation::QuatWxyz => {
+            Rotation::from_quaternion(Quaternion::new(n[0], n[1], n[2], n[3]))
+        }
+        Representation::QuatXyzw => {
+            Rotation::from_quaternion(Quaternion::new(n[3], n[0], n[1], n[2]))
+        }
+        Representation::AxisAngle => {
+            Rotation::from_axis_angle([n[0], n[1], n[2]], n[3] * angle_scale)
+        }
+        Representation::RotationVector => {
+            return Rotation::exp([n[0] * angle_scale, n[1] * angle_scale, n[2] * angle_scale])
+                .ok_or_else(|| format!("{} is too large", from.name()));
+        }
+        Representation::Matrix => {
+            let matrix =
+                RotationMatrix([[n[0], n[1], n[2]], [n[3], n[4], n[5]], [n[6], n[7], n[8]]]);
+            return Rotation::from_matrix(matrix, PASTED_MATRIX_TOLERANCE).ok_or_else(|| {
+                format!(
+                    "matrix is not a rotation (orthogonality error {}, determinant {})",
+                    matrix.orthogonality_error(),
+                    matrix.determinant()
+                )
+            });
+        }
+    };
+    rotation.ok_or_else(|| format!("{} must not be zero", from.name()))
+}
+
+fn format_as(rotation: Rotation, to: Representation, degrees: bool) -> String {
+    let angle_scale = if degrees { 1f32.to_degrees() } else { 1.0 };
+    let q = rotation.quaternion();
+    match to {
+        Representation::QuatWxyz => format_vector(&[q.w, q.x, q.y, q.z]),
+        Representation::QuatXyzw => format_vector(&[q.x, q.y, q.z, q.w]),
+        Representation::AxisAngle => {
+            let ([x, y, z], angle) = rotation.as_axis_angle();
+            format_vector(&[x, y, z, angle * angle_scale])
+        }
+        Representation::RotationVector => {
+            let [x, y, z] = rotation.log();
+            format_vector(&[x * angle_scale, y * angle_scale, z * angle_scale])
+        }
+        Representation::Matrix => {
+            let RotationMatrix(rows) = RotationMatrix::from(rotation);
+            let rows: Vec<String> = rows.iter().map(|row| format_vector(row)).collect();
+            format!("[{}]", rows.join(", "))
+        }
+    }
+}
+
+// Each library's constructor takes the components in its own order: scipy's from_quat is
+// scalar-last, while nalgebra's Quaternion::new and Eigen's Quaternionf ctor are scalar-first.
+fn code_snippet(rotation: Rotation, snippet: Snippet) -> String {
+    let Quaternion { w, x, y, z } = rotation.quaternion();
+    // Adding 0.0 turns -0.0 into 0.0, as in format_vector
+    let [w, x, y, z] = [w, x, y, z].map(|value| value + 0.0);
+    match snippet {
+        Snippet::Scipy => format!(
+            "from scipy.spatial.transform import Rotation\nR = Rotation.from_quat({})",
+            format_vector(&[x, y, z, w])
+        ),
+        Snippet::Nalgebra => format!(
+            "let q = nalgebra::UnitQuaternion::from_quaternion(nalgebra::Quaternion::new({w:?}_f32, {x:?}, {y:?}, {z:?}));"
+        ),
+        Snippet::Eigen => format!("Eigen::Quaternionf q({w:?}f, {x:?}f, {y:?}f, {z:?}f);"),
+    }
+}
+
+fn format_vector(values: &[f32]) -> String {
+    // Adding 0.0 turns -0.0 into 0.0
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| (value + 0.0).to_string())
+        .collect();
+    format!("[{}]", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rotation_visualizer::rotation::TOLERANCE;
+
+    fn rotation() -> Rotation {
+        Rotation::from_quaternion(Quaternion::new(0.8, 0.08, -0.56, 0.2)).unwrap()
+    }
+
+    #[test]
+    fn scipy_snippet_is_scalar_last() {
+        assert_eq!(
+            code_snippet(rotation(), Snippet::Scipy),
+            "from scipy.spatial.transform import Rotation\n\
+             R = Rotation.from_quat([0.08, -0.56, 0.2, 0.8])"
+        );
+    }
+
+    #[test]
+    fn nalgebra_snippet_is_scalar_first() {
+        assert_eq!(
+            code_snippet(rotation(), Snippet::Nalgebra),
+            "let q = nalgebra::UnitQuaternion::from_quaternion(\
+             nalgebra::Quaternion::new(0.8_f32, 0.08, -0.56, 0.2));"
+        );
+    }
+
+    #[test]
+    fn eigen_snippet_is_scalar_first() {
+        assert_eq!(
+            code_snippet(rotation(), Snippet::Eigen),
+            "Eigen::Quaternionf q(0.8f, 0.08f, -0.56f, 0.2f);"
+        );
+    }
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args_reads_options_and_input() {
+        let options = parse(&["--from", "rotvec", "--to", "matrix", "--degrees", "0 0 90"])
+            .unwrap()
+            .unwrap();
+        assert!(options.from == Some(Representation::RotationVector));
+        assert!(options.to == Some(Representation::Matrix));
+        assert!(options.degrees);
+        assert_eq!(options.input.as_deref(), Some("0 0 90"));
+        assert!(parse(&["--from", "auto"]).unwrap().unwrap().from.is_none());
+        assert!(parse(&["--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_args_rejects_bad_usage() {
+        assert_eq!(parse(&["--frob"]).err().unwrap(), "unknown option '--frob'");
+        assert_eq!(
+            parse(&["--to", "matrix", "--snippet", "eigen"])
+                .err()
+                .unwrap(),
+            "--to and --snippet cannot be combined"
+        );
+        assert_eq!(
+            parse(&["1 0 0 0", "0 1 0 0"]).err().unwrap(),
+            "expected a single ROTATION argument (quote it)"
+        );
+        assert!(parse(&["--from", "euler"]).is_err());
+        assert!(parse(&["--to"]).is_err());
+    }
+
+    #[test]
+    fn parse_numbers_ignores_brackets_and_commas() {
+        assert_eq!(
+            parse_numbers("[(1, 2.5); {-3e2}]\n4").unwrap(),
+            vec![1.0, 2.5, -300.0, 4.0]
+        );
+        assert_eq!(parse_numbers("").unwrap(), Vec::<f32>::new());
+    }
+
+    #[test]
+    fn parse_numbers_rejects_non_finite_tokens() {
+        for token in ["abc", "nan", "inf"] {
+            assert_eq!(
+                parse_numbers(&format!("1 {token} 0")).err().unwrap(),
+                format!("'{token}' is not a finite number")
+            );
+        }
+    }
+
+    #[test]
+    fn to_rotation_rejects_malformed_input() {
+        assert_eq!(
+            to_rotation(Representation::QuatWxyz, &[1.0, 0.0, 0.0], false)
+                .err()
+                .unwrap(),
+            "expected 4 numbers for quat-wxyz, got 3"
+        );
+        assert_eq!(
+            to_rotation(Representation::QuatXyzw, &[0.0; 4], false)
+                .err()
+                .unwrap(),
+            "quat-xyzw must not be zero"
+        );
+        let scaled = [2.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0];
+        assert!(
+            to_rotation(Representation::Matrix, &scaled, false)
+                .err()
+                .unwrap()
+                .starts_with("matrix is not a rotation")
+        );
+    }
+
+    #[test]
+    fn to_rotation_converts_degrees() {
+        let rotation =
+            to_rotation(Representation::AxisAngle, &[0.0, 0.0, 2.0, 180.0], true).unwrap();
+        let expected = Rotation::from_axis_angle([0.0, 0.0, 1.0], std::f32::consts::PI).unwrap();
+        assert!(rotation.approx_eq(&expected, TOLERANCE));
+    }
+
+    #[test]
+    fn detect_representation_by_count() {
+        let detect = |numbers: &[f32]| detect_representation(numbers).ok();
+        assert!(detect(&[0.1, 0.2, 0.3]) == Some(Representation::RotationVector));
+        assert!(detect(&[0.5, 0.5, 0.5, 0.5]) == Some(Representation::QuatWxyz));
+        assert!(detect(&[1.0, 0.0, 0.0, 0.0]) == Some(Representation::QuatWxyz));
+        assert!(detect(&[0.0, 0.0, 0.0, 1.0]) == Some(Representation::QuatXyzw));
+        assert!(
+            detect(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]) == Some(Representation::Matrix)
+        );
+        for count in [0, 1, 2, 5, 8, 10] {
+            assert_eq!(
+                detect_representation(&vec![0.0; count]).err().unwrap(),
+                format!("cannot detect a representation from {count} numbers (expected 3, 4 or 9)")
+            );
+        }
+    }
+
+    #[test]
+    fn parse_numbers_accepts_numpy_repr() {
+        let identity = vec![0.0, 0.0, 0.0, 1.0];
+        for input in [
+            "array([0., 0., 0., 1.])",
+            "array([0., 0., 0., 1.], dtype=float32)",
+            "np.array([0, 0, 0, 1])",
+            " numpy.array([0., 0., 0., 1.], dtype=float64)\n",
+        ] {
+            assert_eq!(parse_numbers(input).unwrap(), identity, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_numbers_flattens_nested_brackets() {
+        assert_eq!(
+            parse_numbers("[[0,0,0,1]]").unwrap(),
+            vec![0.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            parse_numbers("[[1, 0], [0, 1]]").unwrap(),
+            vec![1.0, 0.0, 0.0, 1.0]
+        );
+    }
+}
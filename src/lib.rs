@@ -1,11 +1,13 @@
 #![allow(special_module_name)]
+#[cfg(all(feature = "viewer", target_arch = "wasm32"))]
 mod main;
+pub mod rotation;
 
 // Entry point for wasm
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "viewer"))]
 use wasm_bindgen::prelude::*;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "viewer"))]
 #[wasm_bindgen(start)]
 pub fn start() -> Result<(), JsValue> {
     console_log::init_with_level(log::Level::Debug).unwrap();
@@ -1,5 +1,38 @@
 use three_d::*;
 
+// Axis labels, toggled by pressing L, are drawn as line strokes in a unit box centered on the
+// label position, with u pointing right and v pointing up on screen
+const AXIS_LABEL_DISTANCE: f32 = 2.3;
+const AXIS_LABEL_SIZE: f32 = 0.15;
+const AXIS_LABEL_THICKNESS: f32 = 0.01;
+const X_GLYPH: &[[[f32; 2]; 2]] = &[[[-0.5, -0.5], [0.5, 0.5]], [[-0.5, 0.5], [0.5, -0.5]]];
+const Y_GLYPH: &[[[f32; 2]; 2]] = &[
+    [[-0.5, 0.5], [0.0, 0.0]],
+    [[0.5, 0.5], [0.0, 0.0]],
+    [[0.0, 0.0], [0.0, -0.5]],
+];
+const Z_GLYPH: &[[[f32; 2]; 2]] = &[
+    [[-0.5, 0.5], [0.5, 0.5]],
+    [[0.5, 0.5], [-0.5, -0.5]],
+    [[-0.5, -0.5], [0.5, -0.5]],
+];
+
+// Ground grid, toggled by pressing G: lines every GRID_SPACING out to GRID_EXTENT along the two
+// axes spanning the plane, which sits GRID_HEIGHT along its normal (below the lowest shape)
+const GRID_EXTENT: f32 = 3.0;
+const GRID_SPACING: f32 = 0.5;
+const GRID_THICKNESS: f32 = 0.005;
+const GRID_PLANE_AXES: [Vec3; 2] = [vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)];
+const GRID_HEIGHT: f32 = -0.5;
+
+// Initial camera pose, restored by pressing R
+const CAMERA_POSITION: Vec3 = vec3(5.0, 2.0, 2.5);
+const CAMERA_TARGET: Vec3 = vec3(0.0, 0.0, -0.5);
+const CAMERA_UP: Vec3 = vec3(0.0, 1.0, 0.0);
+
+// Background colors cycled by pressing B; the first one is the default
+const BACKGROUND_COLORS: [[f32; 3]; 3] = [[0.8, 0.8, 0.8], [1.0, 1.0, 1.0], [0.0, 0.0, 0.0]];
+
 pub fn main() {
     let window = Window::new(WindowSettings {
         title: "Shapes!".to_string(),
@@ -11,14 +44,14 @@ pub fn main() {
 
     let mut camera = Camera::new_perspective(
         window.viewport(),
-        vec3(5.0, 2.0, 2.5),
-        vec3(0.0, 0.0, -0.5),
-        vec3(0.0, 1.0, 0.0),
+        CAMERA_POSITION,
+        CAMERA_TARGET,
+        CAMERA_UP,
         degrees(45.0),
         0.1,
         1000.0,
     );
-    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut control = OrbitControl::new(CAMERA_TARGET, 1.0, 100.0);
 
     let mut sphere = Gm::new(
         Mesh::new(&context, &CpuMesh::sphere(16)),
@@ -70,6 +103,28 @@ pub fn main() {
     );
     cube.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, 1.3)) * Mat4::from_scale(0.2));
     let axes = Axes::new(&context, 0.1, 2.0);
+    let mut axis_labels = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &axis_label_instances(&camera),
+            &CpuMesh::cylinder(8),
+        ),
+        ColorMaterial::default(),
+    );
+    let grid = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations: grid_transformations(),
+                ..Default::default()
+            },
+            &CpuMesh::cylinder(4),
+        ),
+        ColorMaterial {
+            color: Srgba::new(150, 150, 150, 255),
+            ..Default::default()
+        },
+    );
     let bounding_box_sphere = Gm::new(
         BoundingBox::new(&context, sphere.aabb()),
         ColorMaterial {
@@ -95,20 +150,42 @@ pub fn main() {
     let light0 = DirectionalLight::new(&context, 1.0, Srgba::WHITE, vec3(0.0, -0.5, -0.5));
     let light1 = DirectionalLight::new(&context, 1.0, Srgba::WHITE, vec3(0.0, 0.5, 0.5));
 
+    let mut background_index = 0;
+    let mut show_grid = true;
+    let mut show_axis_labels = true;
     window.render_loop(move |mut frame_input| {
         camera.set_viewport(frame_input.viewport);
+        if key_pressed(&frame_input.events, Key::R) {
+            camera.set_view(CAMERA_POSITION, CAMERA_TARGET, CAMERA_UP);
+            control = OrbitControl::new(CAMERA_TARGET, 1.0, 100.0);
+        }
+        if key_pressed(&frame_input.events, Key::B) {
+            background_index = (background_index + 1) % BACKGROUND_COLORS.len();
+        }
+        if key_pressed(&frame_input.events, Key::G) {
+            show_grid = !show_grid;
+        }
+        if key_pressed(&frame_input.events, Key::L) {
+            show_axis_labels = !show_axis_labels;
+        }
         control.handle_events(&mut camera, &mut frame_input.events);
+        axis_labels
+            .geometry
+            .set_instances(&axis_label_instances(&camera));
 
+        let [r, g, b] = BACKGROUND_COLORS[background_index];
         frame_input
             .screen()
-            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .clear(ClearState::color_and_depth(r, g, b, 1.0, 1.0))
             .render(
                 &camera,
-                sphere
-                    .into_iter()
+                grid.into_iter()
+                    .filter(|_| show_grid)
+                    .chain(&sphere)
                     .chain(&cylinder)
                     .chain(&cube)
                     .chain(&axes)
+                    .chain(axis_labels.into_iter().filter(|_| show_axis_labels))
                     .chain(&bounding_box_sphere)
                     .chain(&bounding_box_cube)
                     .chain(&bounding_box_cylinder),
@@ -118,3 +195,69 @@ pub fn main() {
         FrameOutput::default()
     });
 }
+
+fn key_pressed(events: &[Event], key: Key) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, Event::KeyPress { kind, handled: false, .. } if *kind == key))
+}
+
+fn grid_transformations() -> Vec<Mat4> {
+    let [u, v] = GRID_PLANE_AXES;
+    // v × u rather than u × v so that the default x-z plane's normal points up (+y)
+    let center = v.cross(u) * GRID_HEIGHT;
+    let line_count = (GRID_EXTENT / GRID_SPACING).round() as i32;
+    (-line_count..=line_count)
+        .flat_map(|i| {
+            let offset = i as f32 * GRID_SPACING;
+            [
+                line_transformation(
+                    center + u * offset - v * GRID_EXTENT,
+                    center + u * offset + v * GRID_EXTENT,
+                    GRID_THICKNESS,
+                ),
+                line_transformation(
+                    center + v * offset - u * GRID_EXTENT,
+                    center + v * offset + u * GRID_EXTENT,
+                    GRID_THICKNESS,
+                ),
+            ]
+        })
+        .collect()
+}
+
+// Places "X", "Y" and "Z" just past the tips of the axes, facing the camera and colored like the arrows
+fn axis_label_instances(camera: &Camera) -> Instances {
+    let right = camera.right_direction().normalize() * AXIS_LABEL_SIZE;
+    let up = camera.up_orthogonal().normalize() * AXIS_LABEL_SIZE;
+    let mut transformations = Vec::new();
+    let mut colors = Vec::new();
+    for (direction, glyph, color) in [
+        (vec3(1.0, 0.0, 0.0), X_GLYPH, Srgba::RED),
+        (vec3(0.0, 1.0, 0.0), Y_GLYPH, Srgba::GREEN),
+        (vec3(0.0, 0.0, 1.0), Z_GLYPH, Srgba::BLUE),
+    ] {
+        let center = direction * AXIS_LABEL_DISTANCE;
+        for [[u0, v0], [u1, v1]] in glyph {
+            transformations.push(line_transformation(
+                center + right * *u0 + up * *v0,
+                center + right * *u1 + up * *v1,
+                AXIS_LABEL_THICKNESS,
+            ));
+            colors.push(color);
+        }
+    }
+    Instances {
+        transformations,
+        colors: Some(colors),
+        ..Default::default()
+    }
+}
+
+// Transformation taking the unit cylinder (along x from 0 to 1) to a thin line from `start` to `end`
+fn line_transformation(start: Vec3, end: Vec3, thickness: f32) -> Mat4 {
+    let direction = end - start;
+    Mat4::from_translation(start)
+        * rotation_matrix_from_dir_to_dir(vec3(1.0, 0.0, 0.0), direction.normalize())
+        * Mat4::from_nonuniform_scale(direction.magnitude(), thickness, thickness)
+}
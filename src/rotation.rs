@@ -0,0 +1,603 @@
+use std::ops::{Mul, Neg};
+
+/// Default tolerance for approximate comparisons: radians for [`Rotation::approx_eq`] and
+/// matrix entries for [`RotationMatrix::is_valid_rotation`]. Tune float tolerance here rather
+/// than picking a new epsilon at each call site; the one intended exception is
+/// [`PASTED_MATRIX_TOLERANCE`], which is derived from it.
+pub const TOLERANCE: f32 = 1e-5;
+
+/// Tolerance for validating matrices typed or pasted by users, which are often rounded to a few
+/// decimals: with `0.7071` for `√2/2`, `M·Mᵀ` is already off by more than [`TOLERANCE`].
+pub const PASTED_MATRIX_TOLERANCE: f32 = 100.0 * TOLERANCE;
+
+// Below this norm a quaternion (or its projection onto an axis) is treated as zero
+const ZERO_NORM_THRESHOLD: f32 = 1e-6;
+
+// Below this angle (radians) exp/log use Taylor expansions instead of dividing by the angle
+const SMALL_ANGLE_THRESHOLD: f32 = 1e-2;
+
+/// Default for [`Rotation::as_axis_angle_with_threshold`]: below this `sin(angle / 2)` the axis
+/// is numerically meaningless and the rotation is reported as zero angle about x.
+pub const NEAR_IDENTITY_S_THRESHOLD: f32 = 4.0 * f32::EPSILON;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn norm(&self) -> f32 {
+        (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    /// Returns `None` if the quaternion is (close to) zero.
+    pub fn normalize(&self) -> Option<Quaternion> {
+        let [w, x, y, z] = normalize_vector([self.w, self.x, self.y, self.z])?;
+        Some(Quaternion::new(w, x, y, z))
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+/// Order in which a quaternion's components are written out as a 4-vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuaternionOrder {
+    Wxyz,
+    Xyzw,
+}
+
+impl QuaternionOrder {
+    /// Guesses the order of a positional 4-vector that looks like the identity: one component
+    /// is ±1 and the rest are 0. A ±1 in the first slot suggests `Wxyz`, in the last slot
+    /// `Xyzw`. Returns `None` for anything else, since there is no way to tell in general.
+    pub fn guess(values: [f32; 4]) -> Option<QuaternionOrder> {
+        let is_unit = |v: f32| (v.abs() - 1.0).abs() <= TOLERANCE;
+        let is_zero = |v: f32| v.abs() <= TOLERANCE;
+        match values {
+            [w, x, y, z] if is_unit(w) && is_zero(x) && is_zero(y) && is_zero(z) => {
+                Some(QuaternionOrder::Wxyz)
+            }
+            [x, y, z, w] if is_unit(w) && is_zero(x) && is_zero(y) && is_zero(z) => {
+                Some(QuaternionOrder::Xyzw)
+            }
+            _ => None,
+        }
+    }
+}
+
+// Hamilton product
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+// -q represents the same rotation as q
+impl Neg for Quaternion {
+    type Output = Quaternion;
+
+    fn neg(self) -> Quaternion {
+        Quaternion::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+/// A rotation in 3D, stored as a unit quaternion.
+///
+/// `==` compares the quaternions exactly, so `q` and `-q` (the same rotation) differ; use
+/// [`Rotation::approx_eq`] to compare rotations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotation {
+    quaternion: Quaternion,
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        quaternion: Quaternion::IDENTITY,
+    };
+
+    /// Normalizes `quaternion`; returns `None` if it is (close to) zero.
+    pub fn from_quaternion(quaternion: Quaternion) -> Option<Rotation> {
+        quaternion
+            .normalize()
+            .map(|quaternion| Rotation { quaternion })
+    }
+
+    /// Rotation by `angle` radians about `axis` (which need not be unit length).
+    /// Returns `None` if the axis is (close to) zero.
+    pub fn from_axis_angle(axis: [f32; 3], angle: f32) -> Option<Rotation> {
+        let axis = normalize_vector(axis)?;
+        let (s, c) = (angle / 2.0).sin_cos();
+        Some(Rotation {
+            quaternion: Quaternion::new(c, s * axis[0], s * axis[1], s * axis[2]),
+        })
+    }
+
+    /// Rotation represented by `matrix`; returns `None` unless
+    /// [`RotationMatrix::is_valid_rotation`] passes with `tol`, since pasted matrices may be
+    /// scaled, skewed or reflections.
+    pub fn from_matrix(matrix: RotationMatrix, tol: f32) -> Option<Rotation> {
+        if !matrix.is_valid_rotation(tol) {
+            return None;
+        }
+        let m = &matrix.0;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        // Solve for the largest of |w|, |x|, |y|, |z| first (it is at least 1/2), then divide
+        // the off-diagonal sums and differences by it; this avoids dividing by a tiny value
+        let quaternion = if trace > 0.0 {
+            let s = (1.0 + trace).sqrt() * 2.0;
+            Quaternion::new(
+                s / 4.0,
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+            )
+        } else if m[0][0] >= m[1][1] && m[0][0] >= m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[2][1] - m[1][2]) / s,
+                s / 4.0,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+            )
+        } else if m[1][1] >= m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[0][2] - m[2][0]) / s,
+                (m[0][1] + m[1][0]) / s,
+                s / 4.0,
+                (m[1][2] + m[2][1]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[1][0] - m[0][1]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                s / 4.0,
+            )
+        };
+        Rotation::from_quaternion(quaternion)
+    }
+
+    /// Exponential map from so(3): rotation by `|rotation_vector|` radians about its direction.
+    /// Returns `None` if the vector is not finite or its magnitude overflows.
+    pub fn exp(rotation_vector: [f32; 3]) -> Option<Rotation> {
+        let [x, y, z] = rotation_vector;
+        let angle = vector_norm(rotation_vector);
+        if !angle.is_finite() {
+            return None;
+        }
+        // sin(angle / 2) / angle, which tends to 1/2 as the angle goes to zero
+        let k = if angle < SMALL_ANGLE_THRESHOLD {
+            let angle2 = angle * angle;
+            0.5 - angle2 / 48.0 + angle2 * angle2 / 3840.0
+        } else {
+            (angle / 2.0).sin() / angle
+        };
+        Rotation::from_quaternion(Quaternion::new((angle / 2.0).cos(), k * x, k * y, k * z))
+    }
+
+    /// Logarithm map to so(3), the inverse of [`Rotation::exp`]. The returned rotation vector
+    /// has magnitude in `[0, π]`.
+    pub fn log(&self) -> [f32; 3] {
+        // Pick the w >= 0 representative so the angle is at most π
+        let q = self.with_nonnegative_w().quaternion;
+        let s = (q.x * q.x + q.y * q.y + q.z * q.z).sqrt();
+        // angle / sin(angle / 2), which tends to 2 / w as the angle goes to zero
+        let k = if s < SMALL_ANGLE_THRESHOLD * 0.5 {
+            2.0 / q.w * (1.0 - s * s / (3.0 * q.w * q.w))
+        } else {
+            2.0 * s.atan2(q.w) / s
+        };
+        [k * q.x, k * q.y, k * q.z]
+    }
+
+    pub fn quaternion(&self) -> Quaternion {
+        self.quaternion
+    }
+
+    /// Unit axis and angle in `[0, π]` radians.
+    pub fn as_axis_angle(&self) -> ([f32; 3], f32) {
+        self.as_axis_angle_with_threshold(NEAR_IDENTITY_S_THRESHOLD)
+    }
+
+    /// Like [`Rotation::as_axis_angle`], but rotations whose `sin(angle / 2)` is below
+    /// `threshold` snap to zero angle about the x-axis. A larger threshold keeps the axis from
+    /// jittering for noisy near-identity rotations.
+    pub fn as_axis_angle_with_threshold(&self, threshold: f32) -> ([f32; 3], f32) {
+        let q = self.with_nonnegative_w().quaternion;
+        let s = (q.x * q.x + q.y * q.y + q.z * q.z).sqrt();
+        if s < threshold {
+            return ([1.0, 0.0, 0.0], 0.0);
+        }
+        ([q.x / s, q.y / s, q.z / s], 2.0 * s.atan2(q.w))
+    }
+
+    /// The same rotation with its quaternion negated if needed so that `w >= 0` (q and -q
+    /// represent the same rotation; the w >= 0 one turns by at most π).
+    pub fn with_nonnegative_w(&self) -> Rotation {
+        if self.quaternion.w < 0.0 {
+            Rotation {
+                quaternion: -self.quaternion,
+            }
+        } else {
+            *self
+        }
+    }
+
+    /// Splits the rotation into `(swing, twist)` with `self == swing * twist`, where `twist`
+    /// rotates about `axis` and `swing` rotates about an axis perpendicular to it.
+    ///
+    /// When the rotation is a half-turn about an axis perpendicular to `axis` (or `axis` is zero)
+    /// the twist is undefined; it is then taken to be the identity and the swing is the whole
+    /// rotation.
+    pub fn swing_twist(&self, axis: [f32; 3]) -> (Rotation, Rotation) {
+        let Some(axis) = normalize_vector(axis) else {
+            return (*self, Rotation::IDENTITY);
+        };
+        let q = self.quaternion;
+        let projection = q.x * axis[0] + q.y * axis[1] + q.z * axis[2];
+        let twist = Rotation::from_quaternion(Quaternion::new(
+            q.w,
+            projection * axis[0],
+            projection * axis[1],
+            projection * axis[2],
+        ));
+        match twist {
+            Some(twist) => (*self * twist.inverse(), twist),
+            None => (*self, Rotation::IDENTITY),
+        }
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        Rotation {
+            quaternion: self.quaternion.conjugate(),
+        }
+    }
+
+    /// Angle in radians (in `[0, π]`) of the rotation taking `self` to `other`.
+    pub fn angle_to(&self, other: &Rotation) -> f32 {
+        let difference = self.quaternion.conjugate() * other.quaternion;
+        let s = (difference.x * difference.x
+            + difference.y * difference.y
+            + difference.z * difference.z)
+            .sqrt();
+        2.0 * s.atan2(difference.w.abs())
+    }
+
+    /// Whether the two rotations are within `tol_radians` of each other.
+    ///
+    /// Prefer this over `==`, which compares the stored quaternions exactly: it is sensitive to
+    /// float error and treats `q` and `-q` as different even though they are the same rotation.
+    pub fn approx_eq(&self, other: &Rotation, tol_radians: f32) -> bool {
+        self.angle_to(other) <= tol_radians
+    }
+}
+
+// Composition: `(a * b)` applies `b` first, then `a`
+impl Mul for Rotation {
+    type Output = Rotation;
+
+    fn mul(self, rhs: Rotation) -> Rotation {
+        // Renormalize so float error doesn't accumulate over repeated composition
+        Rotation::from_quaternion(self.quaternion * rhs.quaternion).unwrap_or(Rotation::IDENTITY)
+    }
+}
+
+/// A 3x3 matrix in row-major order, not necessarily a valid rotation (e.g. pasted user data).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationMatrix(pub [[f32; 3]; 3]);
+
+impl RotationMatrix {
+    pub fn determinant(&self) -> f32 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// For a valid rotation this is also its inverse.
+    pub fn transpose(&self) -> RotationMatrix {
+        let m = &self.0;
+        RotationMatrix(std::array::from_fn(|i| std::array::from_fn(|j| m[j][i])))
+    }
+
+    /// Largest absolute entry of `M·Mᵀ − I`; zero for an orthogonal matrix.
+    pub fn orthogonality_error(&self) -> f32 {
+        let m = &self.0;
+        let mut max_error: f32 = 0.0;
+        for i in 0..3 {
+            for j in 0..3 {
+                let product: f32 = (0..3).map(|k| m[i][k] * m[j][k]).sum();
+                let identity = if i == j { 1.0 } else { 0.0 };
+                max_error = max_error.max((product - identity).abs());
+            }
+        }
+        max_error
+    }
+
+    /// Whether the matrix is orthogonal (`M·Mᵀ ≈ I`) with determinant `≈ +1`, each to within
+    /// `tol`. Reflections (determinant -1) and scaled matrices are rejected.
+    pub fn is_valid_rotation(&self, tol: f32) -> bool {
+        self.orthogonality_error() <= tol && (self.determinant() - 1.0).abs() <= tol
+    }
+}
+
+impl From<Rotation> for RotationMatrix {
+    fn from(rotation: Rotation) -> Self {
+        let Quaternion { w, x, y, z } = rotation.quaternion;
+        RotationMatrix([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ])
+    }
+}
+
+// Euclidean norm, scaled by the largest component like `normalize_vector` so that e.g. 2e19
+// doesn't overflow when squared. Infinite only if the norm itself exceeds `f32::MAX`.
+fn vector_norm<const N: usize>(v: [f32; N]) -> f32 {
+    let scale = v.iter().fold(0.0f32, |max, c| max.max(c.abs()));
+    if scale == 0.0 || scale.is_infinite() {
+        return scale;
+    }
+    scale
+        * v.iter()
+            .map(|c| (c / scale) * (c / scale))
+            .sum::<f32>()
+            .sqrt()
+}
+
+// Divides by the largest component before squaring, so huge inputs (e.g. 3e38) don't overflow
+// to an infinite norm and get rejected; only (close to) zero or non-finite input is.
+fn normalize_vector<const N: usize>(v: [f32; N]) -> Option<[f32; N]> {
+    let scale = v.iter().fold(0.0f32, |max, c| max.max(c.abs()));
+    if v.iter().any(|c| !c.is_finite()) || scale == 0.0 {
+        return None;
+    }
+    let scaled = v.map(|c| c / scale);
+    let scaled_norm = scaled.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if scale * scaled_norm < ZERO_NORM_THRESHOLD {
+        return None;
+    }
+    Some(scaled.map(|c| c / scaled_norm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32, tol: f32) {
+        assert!((a - b).abs() <= tol, "{a} != {b} (tol {tol})");
+    }
+
+    #[test]
+    fn swing_twist_of_rotation_about_the_axis_is_all_twist() {
+        let rotation = Rotation::from_axis_angle([0.0, 0.0, 1.0], 0.7).unwrap();
+        let (swing, twist) = rotation.swing_twist([0.0, 0.0, 1.0]);
+        assert!(swing.approx_eq(&Rotation::IDENTITY, TOLERANCE));
+        assert!(twist.approx_eq(&rotation, TOLERANCE));
+    }
+
+    #[test]
+    fn swing_twist_reconstructs_a_mixed_rotation() {
+        let rotation = Rotation::from_axis_angle([1.0, 2.0, 3.0], 2.1).unwrap();
+        let (swing, twist) = rotation.swing_twist([0.0, 0.0, 2.0]);
+        assert!((swing * twist).approx_eq(&rotation, TOLERANCE));
+        let (twist, swing) = (twist.quaternion(), swing.quaternion());
+        assert_close(twist.x, 0.0, TOLERANCE);
+        assert_close(twist.y, 0.0, TOLERANCE);
+        assert_close(swing.z, 0.0, TOLERANCE);
+    }
+
+    #[test]
+    fn swing_twist_of_perpendicular_half_turn_is_all_swing() {
+        let rotation = Rotation::from_axis_angle([1.0, 0.0, 0.0], std::f32::consts::PI).unwrap();
+        let (swing, twist) = rotation.swing_twist([0.0, 0.0, 1.0]);
+        assert_eq!(twist, Rotation::IDENTITY);
+        assert!(swing.approx_eq(&rotation, TOLERANCE));
+    }
+
+    #[test]
+    fn normalize_accepts_huge_components() {
+        let q = Quaternion::new(3e38, 3e38, 0.0, 0.0).normalize().unwrap();
+        assert_close(q.w, std::f32::consts::FRAC_1_SQRT_2, TOLERANCE);
+        assert_close(q.x, std::f32::consts::FRAC_1_SQRT_2, TOLERANCE);
+        assert!(Rotation::from_axis_angle([3e38, 3e38, 0.0], 1.0).is_some());
+        assert_eq!(Quaternion::new(0.0, 0.0, 0.0, 0.0).normalize(), None);
+    }
+
+    #[test]
+    fn exp_log_round_trips_tiny_vector() {
+        let vector = [1e-8, -2e-8, 0.5e-8];
+        let rotation = Rotation::exp(vector).unwrap();
+        assert_close(rotation.quaternion().w, 1.0, TOLERANCE);
+        for (logged, original) in rotation.log().into_iter().zip(vector) {
+            assert_close(logged, original, 1e-12);
+        }
+    }
+
+    #[test]
+    fn exp_log_of_half_turn_has_magnitude_pi() {
+        let [x, y, z] = Rotation::exp([0.0, 0.0, std::f32::consts::PI])
+            .unwrap()
+            .log();
+        assert_close(x, 0.0, TOLERANCE);
+        assert_close(y, 0.0, TOLERANCE);
+        assert_close(z.abs(), std::f32::consts::PI, TOLERANCE);
+    }
+
+    #[test]
+    fn exp_rejects_overflowing_vector() {
+        assert!(Rotation::exp([2e19, 0.0, 0.0]).is_some());
+        assert_eq!(Rotation::exp([3e38, 3e38, 0.0]), None);
+        assert_eq!(Rotation::exp([f32::NAN, 0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn approx_eq_treats_antipodal_quaternions_as_equal() {
+        let q = Quaternion::new(0.5, 0.1, -0.7, 0.3);
+        let rotation = Rotation::from_quaternion(q).unwrap();
+        let antipodal = Rotation::from_quaternion(-q).unwrap();
+        assert_ne!(rotation, antipodal);
+        assert!(rotation.approx_eq(&antipodal, TOLERANCE));
+    }
+
+    #[test]
+    fn approx_eq_rejects_perturbation_beyond_tolerance() {
+        let rotation = Rotation::from_axis_angle([1.0, 2.0, 3.0], 2.1).unwrap();
+        let perturbed = Rotation::from_axis_angle([0.0, 1.0, 0.0], 1e-3).unwrap() * rotation;
+        assert_close(rotation.angle_to(&perturbed), 1e-3, TOLERANCE);
+        assert!(!rotation.approx_eq(&perturbed, 1e-4));
+        assert!(rotation.approx_eq(&perturbed, 2e-3));
+    }
+
+    #[test]
+    fn with_nonnegative_w_flips_to_the_equivalent_quaternion() {
+        let rotation = Rotation::from_quaternion(Quaternion::new(-0.5, 0.5, 0.5, 0.5)).unwrap();
+        let flipped = rotation.with_nonnegative_w();
+        assert!(flipped.quaternion().w >= 0.0);
+        assert_eq!(flipped.quaternion(), Quaternion::new(0.5, -0.5, -0.5, -0.5));
+        assert!(flipped.approx_eq(&rotation, TOLERANCE));
+    }
+
+    #[test]
+    fn as_axis_angle_snaps_below_threshold() {
+        // sin(angle / 2) is about 1e-3
+        let rotation = Rotation::from_axis_angle([0.0, 1.0, 0.0], 2e-3).unwrap();
+        assert_eq!(
+            rotation.as_axis_angle_with_threshold(1.1e-3),
+            ([1.0, 0.0, 0.0], 0.0)
+        );
+        let ([x, y, z], angle) = rotation.as_axis_angle_with_threshold(0.9e-3);
+        assert_close(x, 0.0, TOLERANCE);
+        assert_close(y, 1.0, TOLERANCE);
+        assert_close(z, 0.0, TOLERANCE);
+        assert_close(angle, 2e-3, TOLERANCE);
+    }
+
+    #[test]
+    fn is_valid_rotation_checks_orthogonality_and_determinant() {
+        let identity = RotationMatrix([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert!(identity.is_valid_rotation(TOLERANCE));
+        let scaled = RotationMatrix([[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]);
+        assert!(!scaled.is_valid_rotation(PASTED_MATRIX_TOLERANCE));
+        let reflection = RotationMatrix([[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert!(!reflection.is_valid_rotation(PASTED_MATRIX_TOLERANCE));
+        let skewed = RotationMatrix([[1.0, 1e-4, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert!(skewed.is_valid_rotation(PASTED_MATRIX_TOLERANCE));
+        assert!(!skewed.is_valid_rotation(TOLERANCE));
+    }
+
+    #[test]
+    fn guess_quaternion_order_from_identity() {
+        assert_eq!(
+            QuaternionOrder::guess([1.0, 0.0, 0.0, 0.0]),
+            Some(QuaternionOrder::Wxyz)
+        );
+        assert_eq!(
+            QuaternionOrder::guess([0.0, 0.0, 0.0, 1.0]),
+            Some(QuaternionOrder::Xyzw)
+        );
+        assert_eq!(
+            QuaternionOrder::guess([0.0, 0.0, 0.0, -1.0]),
+            Some(QuaternionOrder::Xyzw)
+        );
+        assert_eq!(QuaternionOrder::guess([0.0, 1.0, 0.0, 0.0]), None);
+        assert_eq!(QuaternionOrder::guess([0.5, 0.5, 0.5, 0.5]), None);
+    }
+
+    #[test]
+    fn from_matrix_rejects_invalid_rotations() {
+        let zero = RotationMatrix([[0.0; 3]; 3]);
+        let scaled = RotationMatrix([[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]);
+        let reflection = RotationMatrix([[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        for matrix in [zero, scaled, reflection] {
+            assert_eq!(Rotation::from_matrix(matrix, TOLERANCE), None);
+        }
+    }
+
+    // Each case forces one branch of from_matrix: the trace-positive one, then half-turns whose
+    // largest diagonal entry is m00, m11 and m22
+    #[test]
+    fn from_matrix_recovers_quaternion_in_each_branch() {
+        let pi = std::f32::consts::PI;
+        let rotations = [
+            Rotation::from_axis_angle([1.0, 2.0, 3.0], 1.0).unwrap(),
+            Rotation::from_axis_angle([1.0, 0.0, 0.0], pi).unwrap(),
+            Rotation::from_axis_angle([0.0, 1.0, 0.0], pi).unwrap(),
+            Rotation::from_axis_angle([0.0, 0.0, 1.0], pi).unwrap(),
+        ];
+        for rotation in rotations {
+            let recovered =
+                Rotation::from_matrix(RotationMatrix::from(rotation), TOLERANCE).unwrap();
+            // Up to sign, since q and -q give the same matrix
+            let dot = recovered.quaternion().dot(&rotation.quaternion());
+            assert_close(dot.abs(), 1.0, TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn dot_matches_norm_and_negation() {
+        let q = Quaternion::new(0.5, 0.1, -0.7, 0.3);
+        assert_close(q.dot(&q), q.norm() * q.norm(), TOLERANCE);
+        assert_eq!(q.dot(&-q), -q.dot(&q));
+        let other = Quaternion::new(-0.2, 0.4, 0.1, 0.9);
+        assert_close(q.dot(&other), -0.1 + 0.04 - 0.07 + 0.27, TOLERANCE);
+    }
+
+    #[test]
+    fn transpose_of_rotation_matrix_is_its_inverse() {
+        let rotation = Rotation::from_axis_angle([1.0, 2.0, 3.0], 2.1).unwrap();
+        let transposed = RotationMatrix::from(rotation).transpose();
+        let inverse = RotationMatrix::from(rotation.inverse());
+        for (row, expected_row) in transposed.0.iter().zip(inverse.0) {
+            for (value, expected) in row.iter().zip(expected_row) {
+                assert_close(*value, expected, TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let matrix = RotationMatrix([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        assert_eq!(
+            matrix.transpose(),
+            RotationMatrix([[1.0, 4.0, 7.0], [2.0, 5.0, 8.0], [3.0, 6.0, 9.0]])
+        );
+    }
+}